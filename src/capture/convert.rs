@@ -0,0 +1,139 @@
+use std::convert::TryFrom;
+use std::{io, mem};
+
+use crate::capture::Format;
+use crate::device::CaptureDevice;
+use crate::io::MmapStream;
+use crate::v4l_sys::*;
+use crate::{Error, Result};
+
+/// Capture stream which converts frames to a caller chosen pixel format
+///
+/// Many cameras only emit compressed or packed formats such as MJPEG or YUYV.
+/// `ConvertStream` wraps an [`MmapStream`](crate::io::MmapStream) together with a
+/// `libv4lconvert` handle so that callers can request an arbitrary destination
+/// [`Format`] (for example RGB24 or BGR24) and receive already decoded frames.
+pub struct ConvertStream<'a> {
+    dev: &'a CaptureDevice,
+    handle: *mut v4lconvert_data,
+
+    /// source format negotiated with the device
+    src: v4l2_format,
+    /// destination format requested by the user
+    dest: v4l2_format,
+
+    /// underlying single-planar capture stream
+    stream: MmapStream<'a>,
+    /// scratch buffer holding the source frame handed to the converter
+    src_buf: Vec<u8>,
+    /// scratch buffer holding the converted frame
+    buf: Vec<u8>,
+}
+
+impl<'a> ConvertStream<'a> {
+    /// Returns a converting stream for the given device
+    ///
+    /// The desired destination format is negotiated against the device using
+    /// `v4lconvert_try_format`, the resulting source format is applied via
+    /// `VIDIOC_S_FMT`, and every captured buffer is converted on the fly.
+    ///
+    /// # Arguments
+    ///
+    /// * `dev` - Capture device to negotiate and capture from
+    /// * `dest` - Desired destination format of the converted frames
+    pub fn new(dev: &'a mut CaptureDevice, dest: Format) -> Result<Self> {
+        let handle = unsafe { v4lconvert_create(dev.fd()) };
+        if handle.is_null() {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::Other,
+                "failed to create v4lconvert handle",
+            )));
+        }
+
+        // libv4lconvert takes `struct v4l2_format *`, reading `.fmt.pix` itself.
+        let dest_pix: v4l2_pix_format = dest.into();
+        let mut dest: v4l2_format = unsafe { mem::zeroed() };
+        dest.type_ = v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE;
+        dest.fmt.pix = dest_pix;
+        let mut src: v4l2_format = unsafe { mem::zeroed() };
+        src.type_ = v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE;
+        let ret = unsafe { v4lconvert_try_format(handle, &mut dest, &mut src) };
+        if ret != 0 {
+            let err = Self::last_error(handle);
+            unsafe { v4lconvert_destroy(handle) };
+            return Err(err);
+        }
+
+        // Apply the negotiated native format to the device through the shared
+        // `Device::set_format` path so the captured buffers match the source.
+        let src_fmt = Format::try_from(unsafe { src.fmt.pix })?;
+        dev.set_format(&src_fmt)?;
+
+        // Reborrow as a shared reference for the lifetime of the stream.
+        let dev: &'a CaptureDevice = dev;
+        let stream = MmapStream::with_buffers(dev, 4)?;
+        let src_buf = vec![0u8; unsafe { src.fmt.pix }.sizeimage as usize];
+        let buf = vec![0u8; unsafe { dest.fmt.pix }.sizeimage as usize];
+
+        Ok(ConvertStream {
+            dev,
+            handle,
+            src,
+            dest,
+            stream,
+            src_buf,
+            buf,
+        })
+    }
+
+    /// Captures the next frame and converts it into the destination format
+    ///
+    /// Returns the converted frame bytes on success.
+    pub fn next(&mut self) -> Result<&[u8]> {
+        let frame = self.stream.next()?;
+
+        // Copy the read-only capture mapping into an owned buffer; libv4lconvert
+        // takes the source as a `*mut u8`, so we avoid casting the shared mapping.
+        let len = frame.data().len();
+        if self.src_buf.len() < len {
+            self.src_buf.resize(len, 0);
+        }
+        self.src_buf[..len].copy_from_slice(frame.data());
+
+        let written = unsafe {
+            v4lconvert_convert(
+                self.handle,
+                &mut self.src,
+                &mut self.dest,
+                self.src_buf.as_mut_ptr(),
+                len as i32,
+                self.buf.as_mut_ptr(),
+                self.buf.len() as i32,
+            )
+        };
+        if written < 0 {
+            return Err(Self::last_error(self.handle));
+        }
+
+        Ok(&self.buf[..written as usize])
+    }
+
+    /// Reads the last error message reported by the converter
+    fn last_error(handle: *mut v4lconvert_data) -> Error {
+        let msg = unsafe {
+            let ptr = v4lconvert_get_error_message(handle);
+            if ptr.is_null() {
+                String::from("unknown v4lconvert error")
+            } else {
+                std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned()
+            }
+        };
+        Error::Io(io::Error::new(io::ErrorKind::Other, msg))
+    }
+}
+
+impl<'a> Drop for ConvertStream<'a> {
+    fn drop(&mut self) {
+        unsafe { v4lconvert_destroy(self.handle) };
+    }
+}
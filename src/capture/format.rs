@@ -1,12 +1,23 @@
 use std::convert::TryFrom;
 use std::{fmt, mem};
 
+use bitflags::bitflags;
+
 use crate::colorspace::Colorspace;
 use crate::field_order::FieldOrder;
 use crate::fourcc::FourCC;
+use crate::transfer::TransferFunction;
 use crate::v4l_sys::*;
 use crate::Quantization;
 
+bitflags! {
+    /// Format flags
+    pub struct Flags: u32 {
+        /// premultiplied alpha channel
+        const PREMUL_ALPHA = 0x1;
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 /// Streaming format (single-planar)
 pub struct Format {
@@ -26,6 +37,10 @@ pub struct Format {
     pub colorspace: Colorspace,
     /// the way colors are mapped
     pub quantization: Quantization,
+    /// transfer function used to encode the pixels
+    pub transfer: TransferFunction,
+    /// additional format flags
+    pub flags: Flags,
 }
 
 impl Format {
@@ -54,6 +69,8 @@ impl Format {
             size: 0,
             colorspace: Colorspace::Default,
             quantization: Quantization::Default,
+            transfer: TransferFunction::Default,
+            flags: Flags::empty(),
         }
     }
 }
@@ -68,23 +85,32 @@ impl fmt::Display for Format {
         writeln!(f, "size         : {}", self.size)?;
         writeln!(f, "colorspace   : {}", self.colorspace)?;
         writeln!(f, "quantization : {}", self.quantization)?;
+        writeln!(f, "transfer     : {}", self.transfer)?;
+        writeln!(f, "flags        : {:?}", self.flags)?;
         Ok(())
     }
 }
 
-impl From<v4l2_pix_format> for Format {
-    fn from(fmt: v4l2_pix_format) -> Self {
-        // Assume that the given format is valid
-        Format {
+impl TryFrom<v4l2_pix_format> for Format {
+    type Error = crate::Error;
+
+    fn try_from(fmt: v4l2_pix_format) -> Result<Self, Self::Error> {
+        Ok(Format {
             width: fmt.width,
             height: fmt.height,
-            field_order: FieldOrder::try_from(fmt.field).expect("Invalid field"),
+            field_order: FieldOrder::try_from(fmt.field)
+                .map_err(|_| crate::Error::UnknownFieldOrder(fmt.field))?,
             fourcc: FourCC::from(fmt.pixelformat),
             stride: fmt.bytesperline,
             size: fmt.sizeimage,
-            colorspace: Colorspace::try_from(fmt.colorspace).expect("Invalid colorspace"),
-            quantization: Quantization::try_from(fmt.quantization).expect("Invalid quantization"),
-        }
+            colorspace: Colorspace::try_from(fmt.colorspace)
+                .map_err(|_| crate::Error::UnknownColorspace(fmt.colorspace))?,
+            quantization: Quantization::try_from(fmt.quantization)
+                .map_err(|_| crate::Error::UnknownQuantization(fmt.quantization))?,
+            transfer: TransferFunction::try_from(fmt.xfer_func)
+                .map_err(|_| crate::Error::UnknownTransferFunction(fmt.xfer_func))?,
+            flags: Flags::from_bits_truncate(fmt.flags),
+        })
     }
 }
 
@@ -103,6 +129,8 @@ impl Into<v4l2_pix_format> for Format {
         fmt.sizeimage = self.size;
         fmt.colorspace = self.colorspace as u32;
         fmt.quantization = self.quantization as u32;
+        fmt.xfer_func = self.transfer as u32;
+        fmt.flags = self.flags.bits();
         fmt
     }
 }
@@ -0,0 +1,121 @@
+use std::convert::TryFrom;
+use std::{fmt, mem};
+
+use crate::colorspace::Colorspace;
+use crate::field_order::FieldOrder;
+use crate::fourcc::FourCC;
+use crate::transfer::TransferFunction;
+use crate::v4l_sys::*;
+use crate::Quantization;
+
+/// Maximum number of planes per multi-planar buffer (`VIDEO_MAX_PLANES`)
+pub const MAX_PLANES: usize = 8;
+
+#[derive(Debug, Copy, Clone)]
+/// Per-plane geometry of a multi-planar format
+pub struct PlaneFormat {
+    /// maximum number of bytes required to store the plane
+    pub size: u32,
+    /// bytes per line
+    pub stride: u32,
+}
+
+#[derive(Debug, Copy, Clone)]
+/// Streaming format (multi-planar)
+///
+/// Mirrors [`Format`](crate::capture::Format) for the multi-planar API where a
+/// single frame is split across up to [`MAX_PLANES`] memory planes, as used by
+/// hardware pipelines advertising formats such as NV12 or YUV420M only in
+/// `MPLANE` form.
+pub struct MultiPlaneFormat {
+    /// width in pixels
+    pub width: u32,
+    /// height in pixels
+    pub height: u32,
+    /// order of fields
+    pub field_order: FieldOrder,
+    /// pixelformat code
+    pub fourcc: FourCC,
+    /// colorspace of the pixels
+    pub colorspace: Colorspace,
+    /// the way colors are mapped
+    pub quantization: Quantization,
+    /// transfer function used to encode the pixels
+    pub transfer: TransferFunction,
+    /// number of active planes
+    pub num_planes: u8,
+    /// per-plane geometry (only the first `num_planes` entries are valid)
+    pub planes: [PlaneFormat; MAX_PLANES],
+}
+
+impl fmt::Display for MultiPlaneFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "width        : {}", self.width)?;
+        writeln!(f, "height       : {}", self.height)?;
+        writeln!(f, "field        : {}", self.field_order)?;
+        writeln!(f, "fourcc       : {}", self.fourcc)?;
+        writeln!(f, "colorspace   : {}", self.colorspace)?;
+        writeln!(f, "quantization : {}", self.quantization)?;
+        writeln!(f, "transfer     : {}", self.transfer)?;
+        writeln!(f, "planes       : {}", self.num_planes)?;
+        for i in 0..self.num_planes as usize {
+            writeln!(
+                f,
+                "  [{}] size : {}, stride : {}",
+                i, self.planes[i].size, self.planes[i].stride
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<v4l2_pix_format_mplane> for MultiPlaneFormat {
+    type Error = crate::Error;
+
+    fn try_from(fmt: v4l2_pix_format_mplane) -> Result<Self, Self::Error> {
+        let mut planes = [PlaneFormat { size: 0, stride: 0 }; MAX_PLANES];
+        for (i, plane) in planes.iter_mut().enumerate() {
+            plane.size = fmt.plane_fmt[i].sizeimage;
+            plane.stride = fmt.plane_fmt[i].bytesperline;
+        }
+
+        Ok(MultiPlaneFormat {
+            width: fmt.width,
+            height: fmt.height,
+            field_order: FieldOrder::try_from(fmt.field)
+                .map_err(|_| crate::Error::UnknownFieldOrder(fmt.field))?,
+            fourcc: FourCC::from(fmt.pixelformat),
+            colorspace: Colorspace::try_from(fmt.colorspace)
+                .map_err(|_| crate::Error::UnknownColorspace(fmt.colorspace))?,
+            quantization: Quantization::try_from(fmt.quantization)
+                .map_err(|_| crate::Error::UnknownQuantization(fmt.quantization))?,
+            transfer: TransferFunction::try_from(fmt.xfer_func)
+                .map_err(|_| crate::Error::UnknownTransferFunction(fmt.xfer_func))?,
+            num_planes: fmt.num_planes,
+            planes,
+        })
+    }
+}
+
+impl Into<v4l2_pix_format_mplane> for MultiPlaneFormat {
+    fn into(self: MultiPlaneFormat) -> v4l2_pix_format_mplane {
+        let mut fmt: v4l2_pix_format_mplane;
+        unsafe {
+            fmt = mem::zeroed();
+        }
+
+        fmt.width = self.width;
+        fmt.height = self.height;
+        fmt.field = self.field_order as u32;
+        fmt.pixelformat = self.fourcc.into();
+        fmt.colorspace = self.colorspace as u32;
+        fmt.quantization = self.quantization as u32;
+        fmt.xfer_func = self.transfer as u32;
+        fmt.num_planes = self.num_planes;
+        for i in 0..MAX_PLANES {
+            fmt.plane_fmt[i].sizeimage = self.planes[i].size;
+            fmt.plane_fmt[i].bytesperline = self.planes[i].stride;
+        }
+        fmt
+    }
+}
@@ -0,0 +1,40 @@
+use std::fmt;
+
+use crate::v4l_sys::*;
+
+#[derive(Debug, Copy, Clone)]
+/// Streaming parameters (capture)
+pub struct Parameters {
+    /// capability flags (e.g. `V4L2_CAP_TIMEPERFRAME`)
+    pub capabilities: u32,
+    /// capture mode flags
+    pub modes: u32,
+    /// desired period between successive frames (numerator)
+    pub interval_num: u32,
+    /// desired period between successive frames (denominator)
+    pub interval_denom: u32,
+}
+
+impl fmt::Display for Parameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "capabilities : {}", self.capabilities)?;
+        writeln!(f, "modes        : {}", self.modes)?;
+        writeln!(
+            f,
+            "interval     : {}/{} [s]",
+            self.interval_num, self.interval_denom
+        )?;
+        Ok(())
+    }
+}
+
+impl From<v4l2_captureparm> for Parameters {
+    fn from(parm: v4l2_captureparm) -> Self {
+        Parameters {
+            capabilities: parm.capability,
+            modes: parm.capturemode,
+            interval_num: parm.timeperframe.numerator,
+            interval_denom: parm.timeperframe.denominator,
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use std::convert::TryFrom;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::{mem, path::Path};
+
+use crate::capture::{Format, Parameters};
+use crate::frameinterval::FrameInterval;
+use crate::framesize::FrameSize;
+use crate::fourcc::FourCC;
+use crate::v4l_sys::*;
+use crate::{v4l2, v4l2::vidioc, Result};
+
+/// Direction (queue kind) of a [`Device`]
+///
+/// Implementors tie a device type to its V4L2 buffer type so that the shared
+/// [`Device`] core can issue the right ioctls for capture vs output nodes.
+pub trait Direction {
+    /// buffer type used by this direction (e.g. `V4L2_BUF_TYPE_VIDEO_CAPTURE`)
+    const BUF_TYPE: u32;
+}
+
+/// Capture direction marker
+pub struct Capture;
+impl Direction for Capture {
+    const BUF_TYPE: u32 = v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE;
+}
+
+/// Output direction marker
+pub struct Output;
+impl Direction for Output {
+    const BUF_TYPE: u32 = v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_OUTPUT;
+}
+
+/// A generic video device
+///
+/// Holds the logic shared by all node kinds (opening, capability querying,
+/// format and frame geometry enumeration). Direction-specific behavior is
+/// selected by the [`Direction`] type parameter; see the [`CaptureDevice`] and
+/// [`OutputDevice`] wrappers.
+pub struct Device<T: Direction> {
+    /// raw handle (file descriptor) of the opened device node
+    fd: std::os::raw::c_int,
+    dir: PhantomData<T>,
+}
+
+impl<T: Direction> Device<T> {
+    /// Returns a device opened at the given path
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the video node (e.g. `/dev/video0`)
+    pub fn with_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let fd = v4l2::open(path.as_ref(), libc::O_RDWR)?;
+        Ok(Device {
+            fd,
+            dir: PhantomData,
+        })
+    }
+
+    /// Returns the underlying file descriptor
+    pub fn fd(&self) -> std::os::raw::c_int {
+        self.fd
+    }
+
+    /// Queries the device capabilities (`VIDIOC_QUERYCAP`)
+    pub fn query_caps(&self) -> Result<v4l2_capability> {
+        let mut caps: v4l2_capability = unsafe { mem::zeroed() };
+        unsafe {
+            v4l2::ioctl(
+                self.fd,
+                vidioc::VIDIOC_QUERYCAP,
+                &mut caps as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+        Ok(caps)
+    }
+
+    /// Enumerates the pixel formats supported by this device (`VIDIOC_ENUM_FMT`)
+    pub fn enum_formats(&self) -> Result<Vec<FourCC>> {
+        let mut formats = Vec::new();
+        let mut index = 0;
+        loop {
+            let mut desc: v4l2_fmtdesc = unsafe { mem::zeroed() };
+            desc.type_ = T::BUF_TYPE;
+            desc.index = index;
+            let res = unsafe {
+                v4l2::ioctl(
+                    self.fd,
+                    vidioc::VIDIOC_ENUM_FMT,
+                    &mut desc as *mut _ as *mut std::os::raw::c_void,
+                )
+            };
+            if res.is_err() {
+                break;
+            }
+            formats.push(FourCC::from(desc.pixelformat));
+            index += 1;
+        }
+        Ok(formats)
+    }
+
+    /// Enumerates the frame sizes for a given pixel format (`VIDIOC_ENUM_FRAMESIZES`)
+    pub fn enum_framesizes(&self, fourcc: FourCC) -> Result<Vec<FrameSize>> {
+        let mut sizes = Vec::new();
+        let mut index = 0;
+        loop {
+            let mut size: v4l2_frmsizeenum = unsafe { mem::zeroed() };
+            size.index = index;
+            size.pixel_format = fourcc.into();
+            let res = unsafe {
+                v4l2::ioctl(
+                    self.fd,
+                    vidioc::VIDIOC_ENUM_FRAMESIZES,
+                    &mut size as *mut _ as *mut std::os::raw::c_void,
+                )
+            };
+            if res.is_err() {
+                break;
+            }
+            sizes.push(FrameSize::from(size));
+            index += 1;
+        }
+        Ok(sizes)
+    }
+
+    /// Enumerates the frame intervals for a format and size (`VIDIOC_ENUM_FRAMEINTERVALS`)
+    pub fn enum_frameintervals(
+        &self,
+        fourcc: FourCC,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<FrameInterval>> {
+        let mut intervals = Vec::new();
+        let mut index = 0;
+        loop {
+            let mut interval: v4l2_frmivalenum = unsafe { mem::zeroed() };
+            interval.index = index;
+            interval.pixel_format = fourcc.into();
+            interval.width = width;
+            interval.height = height;
+            let res = unsafe {
+                v4l2::ioctl(
+                    self.fd,
+                    vidioc::VIDIOC_ENUM_FRAMEINTERVALS,
+                    &mut interval as *mut _ as *mut std::os::raw::c_void,
+                )
+            };
+            if res.is_err() {
+                break;
+            }
+            intervals.push(FrameInterval::from(interval));
+            index += 1;
+        }
+        Ok(intervals)
+    }
+
+    /// Returns the active format of the device (`VIDIOC_G_FMT`)
+    pub fn format(&self) -> Result<Format> {
+        let mut v4l2_fmt: v4l2_format = unsafe { mem::zeroed() };
+        v4l2_fmt.type_ = T::BUF_TYPE;
+        unsafe {
+            v4l2::ioctl(
+                self.fd,
+                vidioc::VIDIOC_G_FMT,
+                &mut v4l2_fmt as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+        Format::try_from(unsafe { v4l2_fmt.fmt.pix })
+    }
+
+    /// Sets the active format of the device (`VIDIOC_S_FMT`)
+    pub fn set_format(&mut self, fmt: &Format) -> Result<Format> {
+        set_format(self.fd, T::BUF_TYPE, fmt)
+    }
+}
+
+/// Applies a [`Format`] to a queue via `VIDIOC_S_FMT`
+///
+/// Shared by the typed [`Device`] and the memory-to-memory queues so the S_FMT
+/// path is defined once rather than per device type.
+pub(crate) fn set_format(
+    fd: std::os::raw::c_int,
+    type_: u32,
+    fmt: &Format,
+) -> Result<Format> {
+    let mut v4l2_fmt: v4l2_format = unsafe { mem::zeroed() };
+    v4l2_fmt.type_ = type_;
+    v4l2_fmt.fmt.pix = (*fmt).into();
+    unsafe {
+        v4l2::ioctl(
+            fd,
+            vidioc::VIDIOC_S_FMT,
+            &mut v4l2_fmt as *mut _ as *mut std::os::raw::c_void,
+        )?;
+    }
+    Format::try_from(unsafe { v4l2_fmt.fmt.pix })
+}
+
+/// A video capture device
+///
+/// Thin typed wrapper around the [`Device`] core which adds capture-only
+/// behavior such as querying streaming [`Parameters`](crate::capture::Parameters).
+pub struct CaptureDevice(Device<Capture>);
+
+impl CaptureDevice {
+    /// Returns a capture device opened at the given path
+    pub fn with_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(CaptureDevice(Device::with_path(path)?))
+    }
+
+    /// Returns the active streaming parameters (`VIDIOC_G_PARM`)
+    pub fn params(&self) -> Result<Parameters> {
+        let mut parm: v4l2_streamparm = unsafe { mem::zeroed() };
+        parm.type_ = Capture::BUF_TYPE;
+        unsafe {
+            v4l2::ioctl(
+                self.0.fd(),
+                vidioc::VIDIOC_G_PARM,
+                &mut parm as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+        Ok(Parameters::from(unsafe { parm.parm.capture }))
+    }
+}
+
+impl Deref for CaptureDevice {
+    type Target = Device<Capture>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for CaptureDevice {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// A video output device
+///
+/// Thin typed wrapper around the [`Device`] core which adds output-only
+/// behavior, notably the blocking [`Write`] path used to feed frames.
+pub struct OutputDevice(Device<Output>);
+
+impl OutputDevice {
+    /// Returns an output device opened at the given path
+    pub fn with_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(OutputDevice(Device::with_path(path)?))
+    }
+}
+
+impl Deref for OutputDevice {
+    type Target = Device<Output>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for OutputDevice {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Write for OutputDevice {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let ret = unsafe {
+            libc::write(
+                self.0.fd(),
+                buf.as_ptr() as *const std::os::raw::c_void,
+                buf.len(),
+            )
+        };
+        if ret < 0 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(ret as usize)
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
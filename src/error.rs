@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+/// Errors which can occur while talking to a device
+#[derive(Debug, Error)]
+pub enum Error {
+    /// an I/O error (failed open or ioctl, no free buffer, or a format converter failure)
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// the device reported an unknown field order
+    #[error("unknown field order: {0}")]
+    UnknownFieldOrder(u32),
+
+    /// the device reported an unknown colorspace
+    #[error("unknown colorspace: {0}")]
+    UnknownColorspace(u32),
+
+    /// the device reported an unknown quantization
+    #[error("unknown quantization: {0}")]
+    UnknownQuantization(u32),
+
+    /// the device reported an unknown transfer function
+    #[error("unknown transfer function: {0}")]
+    UnknownTransferFunction(u32),
+}
+
+/// Convenience alias for results returned by this crate
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,69 @@
+use std::mem;
+
+use crate::v4l_sys::*;
+use crate::{v4l2, v4l2::vidioc, Result};
+
+/// A pool of memory mapped streaming buffers
+///
+/// Encapsulates the `VIDIOC_REQBUFS` + `VIDIOC_QUERYBUF` + `mmap` sequence shared
+/// by the single-planar mmap streaming paths ([`MmapStream`](crate::io::MmapStream)
+/// and the memory-to-memory queues). The multi-planar
+/// [`MmapStreamMPlane`](crate::io::mmap_mplane::MmapStreamMPlane) maps its
+/// per-plane buffers itself and does not use this type.
+pub struct Arena {
+    /// mapped `(pointer, length)` pair for each requested buffer
+    pub buffers: Vec<(*mut u8, usize)>,
+}
+
+impl Arena {
+    /// Requests and maps `count` buffers of the given buffer type
+    ///
+    /// # Arguments
+    ///
+    /// * `fd` - File descriptor of the opened device node
+    /// * `count` - Number of buffers to request
+    /// * `type_` - V4L2 buffer type (capture vs output)
+    pub fn new(fd: std::os::raw::c_int, count: u32, type_: u32) -> Result<Self> {
+        let mut req: v4l2_requestbuffers = unsafe { mem::zeroed() };
+        req.count = count;
+        req.type_ = type_;
+        req.memory = v4l2_memory_V4L2_MEMORY_MMAP;
+        unsafe {
+            v4l2::ioctl(
+                fd,
+                vidioc::VIDIOC_REQBUFS,
+                &mut req as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+
+        let mut buffers = Vec::with_capacity(req.count as usize);
+        for index in 0..req.count {
+            let mut buf: v4l2_buffer = unsafe { mem::zeroed() };
+            buf.type_ = type_;
+            buf.memory = v4l2_memory_V4L2_MEMORY_MMAP;
+            buf.index = index;
+            unsafe {
+                v4l2::ioctl(
+                    fd,
+                    vidioc::VIDIOC_QUERYBUF,
+                    &mut buf as *mut _ as *mut std::os::raw::c_void,
+                )?;
+            }
+
+            let len = buf.length as usize;
+            let ptr = unsafe {
+                v4l2::mmap(
+                    std::ptr::null_mut(),
+                    len,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    fd,
+                    buf.m.offset as libc::off_t,
+                )?
+            };
+            buffers.push((ptr as *mut u8, len));
+        }
+
+        Ok(Arena { buffers })
+    }
+}
@@ -0,0 +1,196 @@
+use std::{io, mem, ptr, slice};
+
+use crate::capture::mplane::MAX_PLANES;
+use crate::device::CaptureDevice;
+use crate::v4l_sys::*;
+use crate::{v4l2, v4l2::vidioc};
+
+/// Memory mapped multi-planar buffer stream
+///
+/// Like [`MmapStream`](crate::io::MmapStream) but drives the multi-planar API:
+/// each `v4l2_buffer` carries a `planes[]` array which is filled on QBUF and
+/// read back on DQBUF, so that per-plane data slices can be handed to callers.
+pub struct MmapStreamMPlane<'a> {
+    dev: &'a CaptureDevice,
+
+    /// mapped memory for every (buffer, plane) pair
+    mappings: Vec<Vec<(*mut u8, usize)>>,
+    /// scratch plane descriptors reused across dequeue calls
+    planes: [v4l2_plane; MAX_PLANES],
+    num_planes: u8,
+    /// whether the queue has been primed and streaming was turned on
+    active: bool,
+    /// buffer currently owned by the caller (re-queued on the next call)
+    held: Option<u32>,
+}
+
+impl<'a> MmapStreamMPlane<'a> {
+    /// Returns a multi-planar stream with the given number of buffers
+    ///
+    /// # Arguments
+    ///
+    /// * `dev` - Capture device ref to get its file descriptor
+    /// * `count` - Number of buffers to request
+    /// * `num_planes` - Number of planes per buffer
+    pub fn with_buffers(
+        dev: &'a CaptureDevice,
+        count: u32,
+        num_planes: u8,
+    ) -> io::Result<Self> {
+        let mut req: v4l2_requestbuffers = unsafe { mem::zeroed() };
+        req.count = count;
+        req.type_ = v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE_MPLANE;
+        req.memory = v4l2_memory_V4L2_MEMORY_MMAP;
+        unsafe {
+            v4l2::ioctl(
+                dev.fd(),
+                vidioc::VIDIOC_REQBUFS,
+                &mut req as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+
+        let mut mappings = Vec::with_capacity(req.count as usize);
+        for index in 0..req.count {
+            let mut planes: [v4l2_plane; MAX_PLANES] = unsafe { mem::zeroed() };
+            let mut buf: v4l2_buffer = unsafe { mem::zeroed() };
+            buf.type_ = v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE_MPLANE;
+            buf.memory = v4l2_memory_V4L2_MEMORY_MMAP;
+            buf.index = index;
+            buf.length = num_planes as u32;
+            buf.m.planes = planes.as_mut_ptr();
+            unsafe {
+                v4l2::ioctl(
+                    dev.fd(),
+                    vidioc::VIDIOC_QUERYBUF,
+                    &mut buf as *mut _ as *mut std::os::raw::c_void,
+                )?;
+            }
+
+            let mut buf_maps = Vec::with_capacity(num_planes as usize);
+            for p in 0..num_planes as usize {
+                let len = planes[p].length as usize;
+                let ptr = unsafe {
+                    v4l2::mmap(
+                        ptr::null_mut(),
+                        len,
+                        libc::PROT_READ | libc::PROT_WRITE,
+                        libc::MAP_SHARED,
+                        dev.fd(),
+                        planes[p].m.mem_offset as libc::off_t,
+                    )?
+                };
+                buf_maps.push((ptr as *mut u8, len));
+            }
+            mappings.push(buf_maps);
+        }
+
+        Ok(MmapStreamMPlane {
+            dev,
+            mappings,
+            planes: unsafe { mem::zeroed() },
+            num_planes,
+            active: false,
+            held: None,
+        })
+    }
+
+    /// Enqueues a single buffer on the capture queue
+    fn queue(&self, index: u32) -> io::Result<()> {
+        let mut planes: [v4l2_plane; MAX_PLANES] = unsafe { mem::zeroed() };
+        let mut buf: v4l2_buffer = unsafe { mem::zeroed() };
+        buf.type_ = v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE_MPLANE;
+        buf.memory = v4l2_memory_V4L2_MEMORY_MMAP;
+        buf.index = index;
+        buf.length = self.num_planes as u32;
+        buf.m.planes = planes.as_mut_ptr();
+        unsafe {
+            v4l2::ioctl(
+                self.dev.fd(),
+                vidioc::VIDIOC_QBUF,
+                &mut buf as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Queues every buffer and turns streaming on
+    ///
+    /// Mirrors the single-planar [`MmapStream`](crate::io::MmapStream), which
+    /// primes and starts the queue internally before the first dequeue.
+    fn start(&mut self) -> io::Result<()> {
+        for index in 0..self.mappings.len() as u32 {
+            self.queue(index)?;
+        }
+
+        let mut type_ = v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE_MPLANE;
+        unsafe {
+            v4l2::ioctl(
+                self.dev.fd(),
+                vidioc::VIDIOC_STREAMON,
+                &mut type_ as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+        self.active = true;
+        Ok(())
+    }
+
+    /// Dequeues the next buffer and returns a data slice per plane
+    ///
+    /// The returned slices stay valid until the following call: the previously
+    /// handed-out buffer is only re-queued to the driver here, never the one
+    /// whose data is about to be returned.
+    pub fn next(&mut self) -> io::Result<Vec<&[u8]>> {
+        if !self.active {
+            self.start()?;
+        }
+
+        // Hand the buffer borrowed by the previous call back to the driver
+        // before dequeuing a fresh one.
+        if let Some(index) = self.held.take() {
+            self.queue(index)?;
+        }
+
+        self.planes = unsafe { mem::zeroed() };
+        let mut buf: v4l2_buffer = unsafe { mem::zeroed() };
+        buf.type_ = v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE_MPLANE;
+        buf.memory = v4l2_memory_V4L2_MEMORY_MMAP;
+        buf.length = self.num_planes as u32;
+        buf.m.planes = self.planes.as_mut_ptr();
+        unsafe {
+            v4l2::ioctl(
+                self.dev.fd(),
+                vidioc::VIDIOC_DQBUF,
+                &mut buf as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+
+        let maps = &self.mappings[buf.index as usize];
+        let mut out = Vec::with_capacity(self.num_planes as usize);
+        for p in 0..self.num_planes as usize {
+            let used = self.planes[p].bytesused as usize;
+            let (ptr, _len) = maps[p];
+            out.push(unsafe { slice::from_raw_parts(ptr, used) });
+        }
+
+        // Keep ownership of this buffer so the slices above stay valid; it is
+        // re-queued on the next call.
+        self.held = Some(buf.index);
+
+        Ok(out)
+    }
+}
+
+impl<'a> Drop for MmapStreamMPlane<'a> {
+    fn drop(&mut self) {
+        if self.active {
+            let mut type_ = v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE_MPLANE;
+            unsafe {
+                let _ = v4l2::ioctl(
+                    self.dev.fd(),
+                    vidioc::VIDIOC_STREAMOFF,
+                    &mut type_ as *mut _ as *mut std::os::raw::c_void,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::{mem, path::Path};
+
+use crate::capture::Format;
+use crate::device;
+use crate::io::arena::Arena;
+use crate::v4l_sys::*;
+use crate::{v4l2, v4l2::vidioc, Error, Result};
+
+/// A memory-to-memory video device
+///
+/// m2m drivers (scalers, deinterlacers and stateful codecs) expose a single
+/// video node with two independent queues: an `OUTPUT` queue which receives the
+/// source frames and a `CAPTURE` queue which yields the processed frames. Both
+/// queues share the same file descriptor but maintain their own buffer pools and
+/// (possibly different) [`Format`].
+pub struct M2mDevice {
+    /// raw handle (file descriptor) of the opened device node
+    fd: std::os::raw::c_int,
+}
+
+impl M2mDevice {
+    /// Returns a m2m device opened at the given path
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the video node (e.g. `/dev/video0`)
+    pub fn with_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let fd = v4l2::open(path.as_ref(), libc::O_RDWR)?;
+        Ok(M2mDevice { fd })
+    }
+
+    /// Sets the format of the `OUTPUT` (input) queue
+    pub fn set_output_format(&mut self, fmt: &Format) -> Result<Format> {
+        self.set_format(fmt, v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_OUTPUT)
+    }
+
+    /// Sets the format of the `CAPTURE` (output) queue
+    pub fn set_capture_format(&mut self, fmt: &Format) -> Result<Format> {
+        self.set_format(fmt, v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE)
+    }
+
+    fn set_format(&mut self, fmt: &Format, type_: u32) -> Result<Format> {
+        device::set_format(self.fd, type_, fmt)
+    }
+
+    /// Returns the underlying file descriptor
+    pub fn fd(&self) -> std::os::raw::c_int {
+        self.fd
+    }
+}
+
+/// A coordinated pair of memory mapped queues on a [`M2mDevice`]
+///
+/// Pushing a source buffer onto the `OUTPUT` queue drives the hardware, and the
+/// transformed frame is then dequeued from the `CAPTURE` queue.
+pub struct M2mStream<'a> {
+    dev: &'a M2mDevice,
+    output: Vec<(*mut u8, usize)>,
+    capture: Vec<(*mut u8, usize)>,
+    /// OUTPUT buffer indices not currently queued to the driver
+    output_free: Vec<u32>,
+    /// whether both queues have been primed and started
+    active: bool,
+}
+
+impl<'a> M2mStream<'a> {
+    /// Allocates independent buffer pools for both queues
+    pub fn with_buffers(dev: &'a M2mDevice, count: u32) -> Result<Self> {
+        let output = Arena::new(dev.fd(), count, v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_OUTPUT)?.buffers;
+        let capture =
+            Arena::new(dev.fd(), count, v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE)?.buffers;
+        let output_free = (0..output.len() as u32).collect();
+        Ok(M2mStream {
+            dev,
+            output,
+            capture,
+            output_free,
+            active: false,
+        })
+    }
+
+    /// Primes the CAPTURE queue and turns streaming on for both queues
+    ///
+    /// m2m requires both queues streaming before the first transform; the
+    /// CAPTURE buffers must be queued up front so the driver has somewhere to
+    /// write the processed frames.
+    fn start(&mut self) -> Result<()> {
+        for index in 0..self.capture.len() as u32 {
+            self.queue(index, 0, v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE)?;
+        }
+        self.stream_on(v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_OUTPUT)?;
+        self.stream_on(v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE)?;
+        self.active = true;
+        Ok(())
+    }
+
+    fn stream_on(&self, type_: u32) -> Result<()> {
+        let mut type_ = type_;
+        unsafe {
+            v4l2::ioctl(
+                self.dev.fd(),
+                vidioc::VIDIOC_STREAMON,
+                &mut type_ as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Transforms a single frame
+    ///
+    /// Copies `src` into a free `OUTPUT` buffer, queues it, and returns the bytes
+    /// produced on the `CAPTURE` queue.
+    pub fn process(&mut self, src: &[u8]) -> Result<Vec<u8>> {
+        if !self.active {
+            self.start()?;
+        }
+
+        // Claim a free OUTPUT buffer, copy the source frame in, and queue it.
+        let out_index = self.output_free.pop().ok_or_else(|| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::WouldBlock,
+                "no free output buffer",
+            ))
+        })?;
+        let (out_ptr, out_len) = self.output[out_index as usize];
+        let copy = src.len().min(out_len);
+        unsafe { std::ptr::copy_nonoverlapping(src.as_ptr(), out_ptr, copy) };
+        self.queue(out_index, copy, v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_OUTPUT)?;
+
+        // Dequeue the processed frame on the CAPTURE side.
+        let (cap_index, used) = self.dequeue(v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE)?;
+        let (cap_ptr, _) = self.capture[cap_index as usize];
+        let out = unsafe { std::slice::from_raw_parts(cap_ptr, used) }.to_vec();
+
+        // Reclaim the consumed OUTPUT buffer and re-queue the CAPTURE buffer.
+        let (done_index, _) = self.dequeue(v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_OUTPUT)?;
+        self.output_free.push(done_index);
+        self.queue(cap_index, 0, v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE)?;
+
+        Ok(out)
+    }
+
+    fn queue(&self, index: u32, bytesused: usize, type_: u32) -> Result<()> {
+        let mut buf: v4l2_buffer = unsafe { mem::zeroed() };
+        buf.type_ = type_;
+        buf.memory = v4l2_memory_V4L2_MEMORY_MMAP;
+        buf.index = index;
+        buf.bytesused = bytesused as u32;
+        unsafe {
+            v4l2::ioctl(
+                self.dev.fd(),
+                vidioc::VIDIOC_QBUF,
+                &mut buf as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn dequeue(&self, type_: u32) -> Result<(u32, usize)> {
+        let mut buf: v4l2_buffer = unsafe { mem::zeroed() };
+        buf.type_ = type_;
+        buf.memory = v4l2_memory_V4L2_MEMORY_MMAP;
+        unsafe {
+            v4l2::ioctl(
+                self.dev.fd(),
+                vidioc::VIDIOC_DQBUF,
+                &mut buf as *mut _ as *mut std::os::raw::c_void,
+            )?;
+        }
+        Ok((buf.index, buf.bytesused as usize))
+    }
+}
+
+impl<'a> Drop for M2mStream<'a> {
+    fn drop(&mut self) {
+        if self.active {
+            for type_ in [
+                v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_OUTPUT,
+                v4l2_buf_type_V4L2_BUF_TYPE_VIDEO_CAPTURE,
+            ] {
+                let mut type_ = type_;
+                unsafe {
+                    let _ = v4l2::ioctl(
+                        self.dev.fd(),
+                        vidioc::VIDIOC_STREAMOFF,
+                        &mut type_ as *mut _ as *mut std::os::raw::c_void,
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::v4l_sys::*;
+
+#[rustfmt::skip]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Transfer function (colorimetry)
+pub enum TransferFunction {
+    Default     = v4l2_xfer_func_V4L2_XFER_FUNC_DEFAULT as isize,
+    Rec709      = v4l2_xfer_func_V4L2_XFER_FUNC_709 as isize,
+    Srgb        = v4l2_xfer_func_V4L2_XFER_FUNC_SRGB as isize,
+    AdobeRgb    = v4l2_xfer_func_V4L2_XFER_FUNC_ADOBERGB as isize,
+    Smpte240M   = v4l2_xfer_func_V4L2_XFER_FUNC_SMPTE240M as isize,
+    None        = v4l2_xfer_func_V4L2_XFER_FUNC_NONE as isize,
+    DciP3       = v4l2_xfer_func_V4L2_XFER_FUNC_DCI_P3 as isize,
+    Smpte2084   = v4l2_xfer_func_V4L2_XFER_FUNC_SMPTE2084 as isize,
+}
+
+impl TryFrom<u32> for TransferFunction {
+    type Error = ();
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        match code {
+            v4l2_xfer_func_V4L2_XFER_FUNC_DEFAULT => Ok(TransferFunction::Default),
+            v4l2_xfer_func_V4L2_XFER_FUNC_709 => Ok(TransferFunction::Rec709),
+            v4l2_xfer_func_V4L2_XFER_FUNC_SRGB => Ok(TransferFunction::Srgb),
+            v4l2_xfer_func_V4L2_XFER_FUNC_ADOBERGB => Ok(TransferFunction::AdobeRgb),
+            v4l2_xfer_func_V4L2_XFER_FUNC_SMPTE240M => Ok(TransferFunction::Smpte240M),
+            v4l2_xfer_func_V4L2_XFER_FUNC_NONE => Ok(TransferFunction::None),
+            v4l2_xfer_func_V4L2_XFER_FUNC_DCI_P3 => Ok(TransferFunction::DciP3),
+            v4l2_xfer_func_V4L2_XFER_FUNC_SMPTE2084 => Ok(TransferFunction::Smpte2084),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for TransferFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferFunction::Default => write!(f, "Default"),
+            TransferFunction::Rec709 => write!(f, "Rec. 709"),
+            TransferFunction::Srgb => write!(f, "sRGB"),
+            TransferFunction::AdobeRgb => write!(f, "Adobe RGB"),
+            TransferFunction::Smpte240M => write!(f, "SMPTE 240M"),
+            TransferFunction::None => write!(f, "None"),
+            TransferFunction::DciP3 => write!(f, "DCI-P3"),
+            TransferFunction::Smpte2084 => write!(f, "SMPTE 2084"),
+        }
+    }
+}